@@ -0,0 +1,1618 @@
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub enum JSONValue {
+    Obj(HashMap<String, JSONValue>),
+    Arr(Vec<JSONValue>),
+    Str(String),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Bool(bool),
+    Null,
+}
+
+// Escapes a string for use as a JSON string literal: `"`, `\`, the named
+// control escapes (`\b \f \n \r \t`), and any other control code point as
+// `\uXXXX`. Everything else is copied through unchanged.
+fn escape_json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// Formats a finite f64 so the result always re-parses as a JSON number:
+// Rust's `f64::to_string` omits the decimal point for whole-number values
+// (e.g. `1.0e21` becomes `"1000000000000000000000"`), which `parse_number`
+// would read back as an integer (I64/U64), or reject as an invalid number
+// once it exceeds `u64::MAX`. Appending `.0` in that case keeps it in the
+// F64 lane on the way back in.
+fn format_f64(n: f64) -> String {
+    let s = n.to_string();
+    if s.contains('.') || s.contains('e') || s.contains('E') {
+        s
+    } else {
+        format!("{s}.0")
+    }
+}
+
+fn write_compact(value: &JSONValue, out: &mut String) {
+    match value {
+        JSONValue::Obj(hm) => {
+            out.push('{');
+            for (i, (key, v)) in hm.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push('"');
+                out.push_str(&escape_json_string(key));
+                out.push_str("\":");
+                write_compact(v, out);
+            }
+            out.push('}');
+        },
+        JSONValue::Arr(v) => {
+            out.push('[');
+            for (i, x) in v.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_compact(x, out);
+            }
+            out.push(']');
+        },
+        JSONValue::Str(s) => {
+            out.push('"');
+            out.push_str(&escape_json_string(s));
+            out.push('"');
+        },
+        JSONValue::I64(n) => out.push_str(&n.to_string()),
+        JSONValue::U64(n) => out.push_str(&n.to_string()),
+        JSONValue::F64(n) => out.push_str(&format_f64(*n)),
+        JSONValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        JSONValue::Null => out.push_str("null"),
+    }
+}
+
+fn write_pretty(value: &JSONValue, out: &mut String, indent: usize, depth: usize) {
+    let pad = " ".repeat(indent * depth);
+    let child_pad = " ".repeat(indent * (depth + 1));
+    match value {
+        JSONValue::Obj(hm) => {
+            if hm.is_empty() {
+                out.push_str("{}");
+                return;
+            }
+            out.push_str("{\n");
+            for (i, (key, v)) in hm.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(",\n");
+                }
+                out.push_str(&child_pad);
+                out.push('"');
+                out.push_str(&escape_json_string(key));
+                out.push_str("\": ");
+                write_pretty(v, out, indent, depth + 1);
+            }
+            out.push('\n');
+            out.push_str(&pad);
+            out.push('}');
+        },
+        JSONValue::Arr(v) => {
+            if v.is_empty() {
+                out.push_str("[]");
+                return;
+            }
+            out.push_str("[\n");
+            for (i, x) in v.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(",\n");
+                }
+                out.push_str(&child_pad);
+                write_pretty(x, out, indent, depth + 1);
+            }
+            out.push('\n');
+            out.push_str(&pad);
+            out.push(']');
+        },
+        _ => write_compact(value, out),
+    }
+}
+
+impl JSONValue {
+    // Serializes to compact JSON with no insignificant whitespace. Named
+    // `to_compact_string` rather than `to_string` so it doesn't shadow the
+    // blanket `ToString` impl from `Display` (which emits the pretty form).
+    pub fn to_compact_string(&self) -> String {
+        let mut out = String::new();
+        write_compact(self, &mut out);
+        out
+    }
+
+    // Serializes to JSON indented by `indent` spaces per nesting level.
+    pub fn to_string_pretty(&self, indent: usize) -> String {
+        let mut out = String::new();
+        write_pretty(self, &mut out, indent, 0);
+        out
+    }
+}
+
+impl fmt::Display for JSONValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_string_pretty(4))
+    }
+}
+
+// A borrowed, allocation-light counterpart to `JSONValue`. Strings and
+// object keys borrow directly from the input buffer when they contain no
+// escapes, and only allocate when an escape like `\n` or `\uXXXX` forces the
+// text to be rewritten.
+#[derive(Debug)]
+pub enum JSONValueRef<'a> {
+    Obj(HashMap<Cow<'a, str>, JSONValueRef<'a>>),
+    Arr(Vec<JSONValueRef<'a>>),
+    Str(Cow<'a, str>),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Bool(bool),
+    Null,
+}
+
+pub type ParseResult<T> = std::result::Result<T, ParseError>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    UnexpectedToken { token: String, pos: usize, line: usize, col: usize },
+    UnexpectedEof { pos: usize, line: usize, col: usize },
+    InvalidNumber { token: String, pos: usize, line: usize, col: usize },
+    InvalidEscape { pos: usize, line: usize, col: usize },
+    TrailingCharacters { pos: usize, line: usize, col: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedToken { token, line, col, .. } =>
+                write!(f, "unexpected token {:?} at line {}, column {}", token, line, col),
+            ParseError::UnexpectedEof { line, col, .. } =>
+                write!(f, "unexpected end of input at line {}, column {}", line, col),
+            ParseError::InvalidNumber { token, line, col, .. } =>
+                write!(f, "invalid number {:?} at line {}, column {}", token, line, col),
+            ParseError::InvalidEscape { line, col, .. } =>
+                write!(f, "invalid escape sequence at line {}, column {}", line, col),
+            ParseError::TrailingCharacters { line, col, .. } =>
+                write!(f, "trailing characters after JSON value at line {}, column {}", line, col),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone)]
+struct Token {
+    text: String,
+    pos: usize,
+    line: usize,
+    col: usize,
+}
+
+// Parses a complete JSON document into an owned `JSONValue`.
+pub fn parse(input: &str) -> ParseResult<JSONValue> {
+    parse_json(input.as_bytes())
+}
+
+fn parse_json(bytes: &[u8]) -> ParseResult<JSONValue> {
+    let mut stream = TokenStream::new(Tokenizer::new(bytes));
+    let value = parse_value(&mut stream)?;
+    if stream.at_end()? {
+        Ok(value)
+    } else {
+        let token = stream.peek()?;
+        Err(ParseError::TrailingCharacters { pos: token.pos, line: token.line, col: token.col })
+    }
+}
+
+fn parse_hex4(bytes: &[u8], start: usize, pos: usize, line: usize, col: usize) -> ParseResult<u16> {
+    let end = start + 4;
+    if end > bytes.len() {
+        return Err(ParseError::InvalidEscape { pos, line, col });
+    }
+    let hex: String = bytes[start..end].iter().map(|&b| b as char).collect();
+    u16::from_str_radix(&hex, 16).map_err(|_| ParseError::InvalidEscape { pos, line, col })
+}
+
+// Scans a byte slice into `Token`s one at a time instead of materializing
+// the whole sequence up front, so parsing a document only ever holds a
+// single token (plus whatever lookahead the parser buffers) in memory at
+// once. `next` drives the same state machine the old all-at-once `tokenize`
+// function used, a byte at a time, emitting a token as soon as one is
+// complete.
+struct Tokenizer<'a> {
+    bytes: &'a [u8],
+    i: usize,
+    line: usize,
+    col: usize,
+    in_string: bool,
+    escape: bool,
+    curr_token: Vec<char>,
+    curr_token_pos: Option<(usize, usize, usize)>,
+    queue: VecDeque<Token>,
+    done: bool,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Tokenizer {
+            bytes,
+            i: 0,
+            line: 1,
+            col: 1,
+            in_string: false,
+            escape: false,
+            curr_token: Vec::new(),
+            curr_token_pos: None,
+            queue: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    fn eof_error(&self) -> ParseError {
+        ParseError::UnexpectedEof { pos: self.bytes.len(), line: self.line, col: self.col }
+    }
+
+    fn push_char(&mut self, c: char, pos: usize, line: usize, col: usize) {
+        if self.curr_token.is_empty() {
+            self.curr_token_pos = Some((pos, line, col));
+        }
+        self.curr_token.push(c);
+    }
+
+    fn flush_curr_token(&mut self) {
+        if !self.curr_token.is_empty() {
+            let (tp, tl, tc) = self.curr_token_pos.take().unwrap();
+            self.queue.push_back(Token { text: self.curr_token.iter().collect(), pos: tp, line: tl, col: tc });
+            self.curr_token.clear();
+        }
+    }
+
+    // Consumes the byte at `self.i` (a `\uXXXX` escape may consume several
+    // more), advancing position/line/col past everything it reads and
+    // enqueuing zero, one, or two completed tokens.
+    fn step(&mut self) -> ParseResult<()> {
+        let pos = self.i;
+        let (start_line, start_col) = (self.line, self.col);
+        let c = char::from_u32(self.bytes[self.i] as u32).unwrap();
+        match c {
+            '\n' | '\r' | '\t' => {
+                // White space. These are ignored by the tokenizer, but mark the
+                // end of the current token..
+                if self.in_string || self.escape {
+                    return Err(ParseError::UnexpectedToken { token: c.to_string(), pos, line: start_line, col: start_col });
+                }
+                self.flush_curr_token();
+            },
+            '{' | '}' | '[' | ']' | ':' | ',' => {
+                // Special separator characters. Treated as normal characters in
+                // strings, but become a single token outside of them.
+                if self.escape {
+                    return Err(ParseError::InvalidEscape { pos, line: start_line, col: start_col });
+                }
+                if self.in_string {
+                    self.push_char(c, pos, start_line, start_col);
+                } else {
+                    self.flush_curr_token();
+                    self.queue.push_back(Token { text: c.to_string(), pos, line: start_line, col: start_col });
+                }
+            },
+            '\\' => {
+                // Backslash. Must be in a string (if valid JSON) and marks that
+                // a character will be escaped. Becomes '\' if already escaped.
+                if !self.in_string {
+                    return Err(ParseError::UnexpectedToken { token: c.to_string(), pos, line: start_line, col: start_col });
+                }
+                if self.escape {
+                    self.push_char(c, pos, start_line, start_col);
+                    self.escape = false;
+                } else {
+                    self.escape = true;
+                }
+            },
+            '/' => {
+                // Forward slash. Can be escaped.
+                if !self.in_string {
+                    return Err(ParseError::UnexpectedToken { token: c.to_string(), pos, line: start_line, col: start_col });
+                }
+                self.push_char(c, pos, start_line, start_col);
+                self.escape = false; // Just in case the '/' was escaped.
+            },
+            'b' | 'f' | 'n' | 'r' | 't' => {
+                // Escape characters in JSON strings. If in a string and
+                // preceded by a backslash, a character such as n becomes \n.
+                if self.escape {
+                    if !self.in_string {
+                        return Err(ParseError::InvalidEscape { pos, line: start_line, col: start_col });
+                    }
+                    let decoded = match c {
+                        'b' => 8u8 as char,
+                        'f' => 12u8 as char,
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        _ => unreachable!(),
+                    };
+                    self.push_char(decoded, pos, start_line, start_col);
+                    self.escape = false;
+                } else {
+                    self.push_char(c, pos, start_line, start_col);
+                }
+            },
+            'u' => {
+                // Unicode escape. Decodes \uXXXX (and surrogate pairs
+                // \uD800-\uDBFF followed by \uDC00-\uDFFF) into the scalar
+                // value they represent.
+                if self.escape {
+                    if !self.in_string {
+                        return Err(ParseError::InvalidEscape { pos, line: start_line, col: start_col });
+                    }
+                    let high = parse_hex4(self.bytes, self.i + 1, pos, start_line, start_col)?;
+                    self.i += 4;
+                    let scalar = if (0xD800..0xDC00).contains(&high) {
+                        if self.bytes.get(self.i + 1).map(|&b| b as char) != Some('\\')
+                            || self.bytes.get(self.i + 2).map(|&b| b as char) != Some('u') {
+                            return Err(ParseError::InvalidEscape { pos, line: start_line, col: start_col });
+                        }
+                        let low = parse_hex4(self.bytes, self.i + 3, pos, start_line, start_col)?;
+                        self.i += 6;
+                        if !(0xDC00..0xE000).contains(&low) {
+                            return Err(ParseError::InvalidEscape { pos, line: start_line, col: start_col });
+                        }
+                        ((high as u32 - 0xD800) << 10) + (low as u32 - 0xDC00) + 0x10000
+                    } else if (0xDC00..0xE000).contains(&high) {
+                        return Err(ParseError::InvalidEscape { pos, line: start_line, col: start_col });
+                    } else {
+                        high as u32
+                    };
+                    let decoded = char::from_u32(scalar)
+                        .ok_or(ParseError::InvalidEscape { pos, line: start_line, col: start_col })?;
+                    self.push_char(decoded, pos, start_line, start_col);
+                    self.escape = false;
+                } else {
+                    self.push_char(c, pos, start_line, start_col);
+                }
+            },
+            '\"' => {
+                // Quotes. Signify the start/end of a string, but not if they
+                // are escaped inside of a string.
+                if self.escape {
+                    if !self.in_string {
+                        return Err(ParseError::InvalidEscape { pos, line: start_line, col: start_col });
+                    }
+                    self.push_char(c, pos, start_line, start_col);
+                    self.escape = false;
+                } else {
+                    self.in_string = !self.in_string;
+                    self.flush_curr_token();
+                    self.queue.push_back(Token { text: c.to_string(), pos, line: start_line, col: start_col });
+                }
+            },
+            ' ' => {
+                // Spaces are ignored if they are not part of a string
+                if self.escape {
+                    return Err(ParseError::InvalidEscape { pos, line: start_line, col: start_col });
+                }
+                if self.in_string {
+                    self.push_char(c, pos, start_line, start_col);
+                } else {
+                    self.flush_curr_token();
+                }
+            },
+            _ => {
+                // Other characters. These just combine into numbers/words/other
+                if self.escape {
+                    return Err(ParseError::InvalidEscape { pos, line: start_line, col: start_col });
+                }
+                self.push_char(c, pos, start_line, start_col);
+            },
+        }
+
+        for &b in &self.bytes[pos..=self.i] {
+            if b == b'\n' {
+                self.line += 1;
+                self.col = 1;
+            } else {
+                self.col += 1;
+            }
+        }
+        self.i += 1;
+        Ok(())
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = ParseResult<Token>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(tok) = self.queue.pop_front() {
+                return Some(Ok(tok));
+            }
+            if self.done {
+                return None;
+            }
+            if self.i >= self.bytes.len() {
+                self.done = true;
+                if self.in_string {
+                    return Some(Err(self.eof_error()));
+                }
+                if !self.curr_token.is_empty() {
+                    // A final token with no trailing whitespace/separator to
+                    // flush it, e.g. a bare top-level scalar like `true` or
+                    // `42`. It's complete, not truncated, so flush it instead
+                    // of reporting EOF.
+                    self.flush_curr_token();
+                    continue;
+                }
+                return None;
+            }
+            if let Err(e) = self.step() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+// Wraps a `Tokenizer` with a single token of lookahead, so the recursive
+// descent parser below can peek the current token before deciding whether
+// to consume it.
+struct TokenStream<'a> {
+    tokenizer: Tokenizer<'a>,
+    lookahead: Option<Token>,
+    // Set once the tokenizer has cleanly run out of tokens, distinct from
+    // `lookahead` simply not having been filled in yet.
+    exhausted: bool,
+}
+
+impl<'a> TokenStream<'a> {
+    fn new(tokenizer: Tokenizer<'a>) -> Self {
+        TokenStream { tokenizer, lookahead: None, exhausted: false }
+    }
+
+    // Returns the current token without consuming it. Errors if the stream
+    // is exhausted or the tokenizer hit a genuine error.
+    fn peek(&mut self) -> ParseResult<&Token> {
+        self.fill()?;
+        self.lookahead.as_ref().ok_or_else(|| self.tokenizer.eof_error())
+    }
+
+    // Discards the current token so the next `peek`/`advance` pulls a fresh one.
+    fn advance(&mut self) -> ParseResult<()> {
+        self.peek()?;
+        self.lookahead = None;
+        Ok(())
+    }
+
+    // Reports whether the stream is cleanly exhausted (no more tokens, no
+    // error), as opposed to having trailing content left to reject — which
+    // may itself be a malformed token rather than a valid one. Used by
+    // `parse_json` to tell "clean end of input" apart from "tokenizer error
+    // in the trailing bytes", which `peek`/`advance` alone conflate because
+    // both surface as `Err`.
+    fn at_end(&mut self) -> ParseResult<bool> {
+        self.fill()?;
+        Ok(self.lookahead.is_none())
+    }
+
+    // Ensures `lookahead` holds the next token, if any remain, without
+    // treating "no more tokens" as an error.
+    fn fill(&mut self) -> ParseResult<()> {
+        if self.lookahead.is_some() || self.exhausted {
+            return Ok(());
+        }
+        match self.tokenizer.next() {
+            None => self.exhausted = true,
+            Some(Ok(token)) => self.lookahead = Some(token),
+            Some(Err(e)) => return Err(e),
+        }
+        Ok(())
+    }
+}
+
+fn current_token(stream: &mut TokenStream) -> ParseResult<Token> {
+    stream.peek().cloned()
+}
+
+fn parse_value(stream: &mut TokenStream) -> ParseResult<JSONValue> {
+    let token = current_token(stream)?;
+    match token.text.as_str() {
+        "{" => {
+            // Parsing object
+            stream.advance()?;
+            parse_object(stream)
+        },
+        "[" => {
+            // Parsing array
+            stream.advance()?;
+            parse_array(stream)
+        },
+        "\"" => {
+            // Parsing string
+            stream.advance()?;
+            Ok(JSONValue::Str(parse_string(stream)?))
+        },
+        "true" => {
+            // Parsing true value
+            stream.advance()?;
+            Ok(JSONValue::Bool(true))
+        },
+        "false" => {
+            // Parsing false value
+            stream.advance()?;
+            Ok(JSONValue::Bool(false))
+        },
+        "null" => {
+            // Parsing null value
+            stream.advance()?;
+            Ok(JSONValue::Null)
+        },
+        _ => {
+            // Parsing number
+            parse_number(stream)
+        },
+    }
+}
+
+fn parse_object(stream: &mut TokenStream) -> ParseResult<JSONValue> {
+    let mut hm = HashMap::<String, JSONValue>::new();
+
+    // An empty object is the only place a "}" may appear without a
+    // preceding key — checked once up front so a trailing comma can't
+    // loop back around into this same case and be silently accepted.
+    if current_token(stream)?.text == "}" {
+        stream.advance()?;
+        return Ok(JSONValue::Obj(hm));
+    }
+
+    loop {
+        let token = current_token(stream)?;
+        if token.text != "\"" {
+            return Err(ParseError::UnexpectedToken { token: token.text.clone(), pos: token.pos, line: token.line, col: token.col });
+        }
+        stream.advance()?;
+        let key = parse_string(stream)?;
+        let colon = current_token(stream)?;
+        if colon.text != ":" {
+            return Err(ParseError::UnexpectedToken { token: colon.text.clone(), pos: colon.pos, line: colon.line, col: colon.col });
+        }
+        stream.advance()?;
+        let value = parse_value(stream)?;
+        hm.insert(key, value);
+
+        let token = current_token(stream)?;
+        match token.text.as_str() {
+            "," => {
+                // New entry
+                stream.advance()?;
+                continue;
+            },
+            "}" => {
+                // End of object
+                stream.advance()?;
+                break;
+            },
+            _ => return Err(ParseError::UnexpectedToken { token: token.text.clone(), pos: token.pos, line: token.line, col: token.col }),
+        }
+    }
+    Ok(JSONValue::Obj(hm))
+}
+
+fn parse_array(stream: &mut TokenStream) -> ParseResult<JSONValue> {
+    let mut array = Vec::<JSONValue>::new();
+
+    // An empty array is the only place a "]" may appear without a
+    // preceding value — checked once up front so a trailing comma can't
+    // loop back around into this same case and be silently accepted.
+    if current_token(stream)?.text == "]" {
+        stream.advance()?;
+        return Ok(JSONValue::Arr(array));
+    }
+
+    loop {
+        let value = parse_value(stream)?;
+        array.push(value);
+
+        let token = current_token(stream)?;
+        match token.text.as_str() {
+            "," => {
+                // New entry
+                stream.advance()?;
+                continue;
+            },
+            "]" => {
+                // End of array
+                stream.advance()?;
+                break;
+            },
+            _ => return Err(ParseError::UnexpectedToken { token: token.text.clone(), pos: token.pos, line: token.line, col: token.col }),
+        }
+    }
+    Ok(JSONValue::Arr(array))
+}
+
+fn parse_string(stream: &mut TokenStream) -> ParseResult<String> {
+    let curr = current_token(stream)?;
+    let curr_text = curr.text.clone();
+    stream.advance()?;
+    let next = current_token(stream)?;
+
+    match next.text.as_str() {
+        "\"" => {
+            stream.advance()?;
+            Ok(curr_text)
+        },
+        _ => Ok(String::new()),
+    }
+}
+
+// Validates the strict JSON number grammar: an optional `-`, an integer part
+// that is either `0` or a non-zero digit followed by more digits (no leading
+// zeros), an optional `.` fraction, and an optional `e`/`E` exponent.
+fn is_valid_number_syntax(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+
+    if bytes.get(i) == Some(&b'-') {
+        i += 1;
+    }
+
+    match bytes.get(i) {
+        Some(b'0') => i += 1,
+        Some(b) if b.is_ascii_digit() => {
+            while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+                i += 1;
+            }
+        },
+        _ => return false,
+    }
+
+    if bytes.get(i) == Some(&b'.') {
+        i += 1;
+        let frac_start = i;
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        if frac_start == i {
+            return false;
+        }
+    }
+
+    if matches!(bytes.get(i), Some(b'e') | Some(b'E')) {
+        i += 1;
+        if matches!(bytes.get(i), Some(b'+') | Some(b'-')) {
+            i += 1;
+        }
+        let exp_start = i;
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        if exp_start == i {
+            return false;
+        }
+    }
+
+    i == bytes.len()
+}
+
+fn parse_number(stream: &mut TokenStream) -> ParseResult<JSONValue> {
+    let token = current_token(stream)?;
+    let text = token.text.as_str();
+    let invalid = || ParseError::InvalidNumber { token: token.text.clone(), pos: token.pos, line: token.line, col: token.col };
+
+    if !is_valid_number_syntax(text) {
+        return Err(invalid());
+    }
+
+    let value = if text.contains('.') || text.contains('e') || text.contains('E') {
+        let n = f64::from_str(text).map_err(|_| invalid())?;
+        if !n.is_finite() {
+            return Err(invalid());
+        }
+        JSONValue::F64(n)
+    } else if let Ok(n) = i64::from_str(text) {
+        JSONValue::I64(n)
+    } else {
+        JSONValue::U64(u64::from_str(text).map_err(|_| invalid())?)
+    };
+
+    stream.advance()?;
+    Ok(value)
+}
+
+// Computes the 1-indexed line and column of a byte offset, for error
+// reporting in the borrowed parser (which scans raw bytes instead of
+// threading a running line/col counter through a token pass).
+fn line_col_at(bytes: &[u8], pos: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut col = 1;
+    for &b in &bytes[..pos.min(bytes.len())] {
+        if b == b'\n' {
+            line += 1;
+            col = 1;
+        } else {
+            col += 1;
+        }
+    }
+    (line, col)
+}
+
+fn skip_ws_ref(bytes: &[u8], cursor: &Cell<usize>) {
+    while let Some(b' ' | b'\t' | b'\n' | b'\r') = bytes.get(cursor.get()) {
+        cursor.set(cursor.get() + 1);
+    }
+}
+
+fn expect_literal_ref(bytes: &[u8], cursor: &Cell<usize>, literal: &str) -> ParseResult<()> {
+    let start = cursor.get();
+    let end = start + literal.len();
+    if bytes.get(start..end) != Some(literal.as_bytes()) {
+        let (line, col) = line_col_at(bytes, start);
+        let found: String = bytes.get(start..end.min(bytes.len()))
+            .map(|s| String::from_utf8_lossy(s).into_owned())
+            .unwrap_or_default();
+        return Err(ParseError::UnexpectedToken { token: found, pos: start, line, col });
+    }
+    cursor.set(end);
+    Ok(())
+}
+
+// Decodes a single escape sequence starting right after the backslash.
+// Returns the decoded character and how many bytes (excluding the leading
+// backslash) the sequence occupies.
+fn decode_escape_ref(bytes: &[u8], i: usize, err_pos: usize) -> ParseResult<(char, usize)> {
+    let invalid = || {
+        let (line, col) = line_col_at(bytes, err_pos);
+        ParseError::InvalidEscape { pos: err_pos, line, col }
+    };
+    match bytes.get(i).copied() {
+        Some(b'"') => Ok(('"', 1)),
+        Some(b'\\') => Ok(('\\', 1)),
+        Some(b'/') => Ok(('/', 1)),
+        Some(b'b') => Ok((8u8 as char, 1)),
+        Some(b'f') => Ok((12u8 as char, 1)),
+        Some(b'n') => Ok(('\n', 1)),
+        Some(b'r') => Ok(('\r', 1)),
+        Some(b't') => Ok(('\t', 1)),
+        Some(b'u') => {
+            let (line, col) = line_col_at(bytes, err_pos);
+            let high = parse_hex4(bytes, i + 1, err_pos, line, col)?;
+            if (0xD800..0xDC00).contains(&high) {
+                if bytes.get(i + 5) != Some(&b'\\') || bytes.get(i + 6) != Some(&b'u') {
+                    return Err(invalid());
+                }
+                let low = parse_hex4(bytes, i + 7, err_pos, line, col)?;
+                if !(0xDC00..0xE000).contains(&low) {
+                    return Err(invalid());
+                }
+                let scalar = ((high as u32 - 0xD800) << 10) + (low as u32 - 0xDC00) + 0x10000;
+                let ch = char::from_u32(scalar).ok_or_else(invalid)?;
+                Ok((ch, 11))
+            } else if (0xDC00..0xE000).contains(&high) {
+                Err(invalid())
+            } else {
+                let ch = char::from_u32(high as u32).ok_or_else(invalid)?;
+                Ok((ch, 5))
+            }
+        },
+        _ => Err(invalid()),
+    }
+}
+
+// Scans a JSON string literal, borrowing it straight out of `input` when it
+// contains no escapes, and falling back to an owned, decoded `String` only
+// when an escape forces the text to be rewritten.
+fn parse_string_ref<'a>(input: &'a str, cursor: &Cell<usize>) -> ParseResult<Cow<'a, str>> {
+    let bytes = input.as_bytes();
+    let quote_pos = cursor.get();
+    let content_start = quote_pos + 1;
+    let mut i = content_start;
+    let mut has_escape = false;
+    while i < bytes.len() && bytes[i] != b'"' {
+        if bytes[i] == b'\\' {
+            has_escape = true;
+            i += 1;
+        }
+        i += 1;
+    }
+    if i >= bytes.len() {
+        let (line, col) = line_col_at(bytes, quote_pos);
+        return Err(ParseError::UnexpectedEof { pos: quote_pos, line, col });
+    }
+    let content_end = i;
+    cursor.set(i + 1);
+
+    if !has_escape {
+        return Ok(Cow::Borrowed(&input[content_start..content_end]));
+    }
+
+    let mut out = String::with_capacity(content_end - content_start);
+    let mut j = content_start;
+    while j < content_end {
+        if bytes[j] == b'\\' {
+            let (decoded, consumed) = decode_escape_ref(bytes, j + 1, quote_pos)?;
+            out.push(decoded);
+            j += 1 + consumed;
+        } else {
+            let ch = input[j..].chars().next().unwrap();
+            out.push(ch);
+            j += ch.len_utf8();
+        }
+    }
+    Ok(Cow::Owned(out))
+}
+
+fn parse_number_ref(input: &str, cursor: &Cell<usize>) -> ParseResult<JSONValueRef<'static>> {
+    let bytes = input.as_bytes();
+    let start = cursor.get();
+    let mut i = start;
+    while bytes.get(i).is_some_and(|b| matches!(b, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E')) {
+        i += 1;
+    }
+    let text = &input[start..i];
+    let (line, col) = line_col_at(bytes, start);
+    let invalid = || ParseError::InvalidNumber { token: text.to_string(), pos: start, line, col };
+
+    if !is_valid_number_syntax(text) {
+        return Err(invalid());
+    }
+
+    let value = if text.contains('.') || text.contains('e') || text.contains('E') {
+        let n = f64::from_str(text).map_err(|_| invalid())?;
+        if !n.is_finite() {
+            return Err(invalid());
+        }
+        JSONValueRef::F64(n)
+    } else if let Ok(n) = i64::from_str(text) {
+        JSONValueRef::I64(n)
+    } else {
+        JSONValueRef::U64(u64::from_str(text).map_err(|_| invalid())?)
+    };
+
+    cursor.set(i);
+    Ok(value)
+}
+
+fn parse_value_ref<'a>(input: &'a str, cursor: &Cell<usize>) -> ParseResult<JSONValueRef<'a>> {
+    let bytes = input.as_bytes();
+    skip_ws_ref(bytes, cursor);
+    match bytes.get(cursor.get()) {
+        Some(b'{') => {
+            cursor.set(cursor.get() + 1);
+            parse_object_ref(input, cursor)
+        },
+        Some(b'[') => {
+            cursor.set(cursor.get() + 1);
+            parse_array_ref(input, cursor)
+        },
+        Some(b'"') => Ok(JSONValueRef::Str(parse_string_ref(input, cursor)?)),
+        Some(b't') => {
+            expect_literal_ref(bytes, cursor, "true")?;
+            Ok(JSONValueRef::Bool(true))
+        },
+        Some(b'f') => {
+            expect_literal_ref(bytes, cursor, "false")?;
+            Ok(JSONValueRef::Bool(false))
+        },
+        Some(b'n') => {
+            expect_literal_ref(bytes, cursor, "null")?;
+            Ok(JSONValueRef::Null)
+        },
+        Some(_) => Ok(match parse_number_ref(input, cursor)? {
+            JSONValueRef::I64(n) => JSONValueRef::I64(n),
+            JSONValueRef::U64(n) => JSONValueRef::U64(n),
+            JSONValueRef::F64(n) => JSONValueRef::F64(n),
+            _ => unreachable!(),
+        }),
+        None => {
+            let (line, col) = line_col_at(bytes, bytes.len());
+            Err(ParseError::UnexpectedEof { pos: bytes.len(), line, col })
+        },
+    }
+}
+
+fn parse_object_ref<'a>(input: &'a str, cursor: &Cell<usize>) -> ParseResult<JSONValueRef<'a>> {
+    let bytes = input.as_bytes();
+    let mut hm = HashMap::new();
+    skip_ws_ref(bytes, cursor);
+    if bytes.get(cursor.get()) == Some(&b'}') {
+        cursor.set(cursor.get() + 1);
+        return Ok(JSONValueRef::Obj(hm));
+    }
+    loop {
+        skip_ws_ref(bytes, cursor);
+        let quote_pos = cursor.get();
+        if bytes.get(quote_pos) != Some(&b'"') {
+            let (line, col) = line_col_at(bytes, quote_pos);
+            let token = bytes.get(quote_pos).map(|&b| (b as char).to_string()).unwrap_or_default();
+            return Err(ParseError::UnexpectedToken { token, pos: quote_pos, line, col });
+        }
+        let key = parse_string_ref(input, cursor)?;
+        skip_ws_ref(bytes, cursor);
+        let colon_pos = cursor.get();
+        if bytes.get(colon_pos) != Some(&b':') {
+            let (line, col) = line_col_at(bytes, colon_pos);
+            let token = bytes.get(colon_pos).map(|&b| (b as char).to_string()).unwrap_or_default();
+            return Err(ParseError::UnexpectedToken { token, pos: colon_pos, line, col });
+        }
+        cursor.set(colon_pos + 1);
+        let value = parse_value_ref(input, cursor)?;
+        hm.insert(key, value);
+
+        skip_ws_ref(bytes, cursor);
+        match bytes.get(cursor.get()) {
+            Some(b',') => {
+                cursor.set(cursor.get() + 1);
+                continue;
+            },
+            Some(b'}') => {
+                cursor.set(cursor.get() + 1);
+                break;
+            },
+            _ => {
+                let pos = cursor.get();
+                let (line, col) = line_col_at(bytes, pos);
+                let token = bytes.get(pos).map(|&b| (b as char).to_string()).unwrap_or_default();
+                return Err(ParseError::UnexpectedToken { token, pos, line, col });
+            },
+        }
+    }
+    Ok(JSONValueRef::Obj(hm))
+}
+
+fn parse_array_ref<'a>(input: &'a str, cursor: &Cell<usize>) -> ParseResult<JSONValueRef<'a>> {
+    let bytes = input.as_bytes();
+    let mut arr = Vec::new();
+    skip_ws_ref(bytes, cursor);
+    if bytes.get(cursor.get()) == Some(&b']') {
+        cursor.set(cursor.get() + 1);
+        return Ok(JSONValueRef::Arr(arr));
+    }
+    loop {
+        let value = parse_value_ref(input, cursor)?;
+        arr.push(value);
+
+        skip_ws_ref(bytes, cursor);
+        match bytes.get(cursor.get()) {
+            Some(b',') => {
+                cursor.set(cursor.get() + 1);
+                continue;
+            },
+            Some(b']') => {
+                cursor.set(cursor.get() + 1);
+                break;
+            },
+            _ => {
+                let pos = cursor.get();
+                let (line, col) = line_col_at(bytes, pos);
+                let token = bytes.get(pos).map(|&b| (b as char).to_string()).unwrap_or_default();
+                return Err(ParseError::UnexpectedToken { token, pos, line, col });
+            },
+        }
+    }
+    Ok(JSONValueRef::Arr(arr))
+}
+
+// Parses a complete JSON document into a borrowed `JSONValueRef` that
+// reuses slices of `input` wherever possible instead of allocating.
+pub fn parse_ref<'a>(input: &'a str) -> ParseResult<JSONValueRef<'a>> {
+    let bytes = input.as_bytes();
+    let cursor = Cell::new(0usize);
+    let value = parse_value_ref(input, &cursor)?;
+    skip_ws_ref(bytes, &cursor);
+    if cursor.get() != bytes.len() {
+        let (line, col) = line_col_at(bytes, cursor.get());
+        return Err(ParseError::TrailingCharacters { pos: cursor.get(), line, col });
+    }
+    Ok(value)
+}
+
+pub type PathResult<T> = std::result::Result<T, PathError>;
+
+// A malformed JSONPath expression. Unlike `ParseError`, positions are a
+// character offset into the path string rather than the document being
+// queried.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathError {
+    pub pos: usize,
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "malformed JSONPath expression at character {}", self.pos)
+    }
+}
+
+impl std::error::Error for PathError {}
+
+#[derive(Debug)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+    Recursive(String),
+}
+
+fn is_path_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn tokenize_path(path: &str) -> PathResult<Vec<PathSegment>> {
+    let chars: Vec<char> = path.chars().collect();
+    let mut segments = Vec::new();
+    let mut i = 0;
+
+    if chars.get(i) == Some(&'$') {
+        i += 1;
+    }
+
+    while i < chars.len() {
+        match chars[i] {
+            '.' => {
+                i += 1;
+                if chars.get(i) == Some(&'.') {
+                    // Recursive descent: `..key`.
+                    i += 1;
+                    let start = i;
+                    while i < chars.len() && is_path_ident_char(chars[i]) {
+                        i += 1;
+                    }
+                    if start == i {
+                        return Err(PathError { pos: i });
+                    }
+                    segments.push(PathSegment::Recursive(chars[start..i].iter().collect()));
+                } else if chars.get(i) == Some(&'*') {
+                    i += 1;
+                    segments.push(PathSegment::Wildcard);
+                } else {
+                    let start = i;
+                    while i < chars.len() && is_path_ident_char(chars[i]) {
+                        i += 1;
+                    }
+                    if start == i {
+                        return Err(PathError { pos: i });
+                    }
+                    segments.push(PathSegment::Key(chars[start..i].iter().collect()));
+                }
+            },
+            '[' => {
+                i += 1;
+                if chars.get(i) == Some(&'*') {
+                    i += 1;
+                    if chars.get(i) != Some(&']') {
+                        return Err(PathError { pos: i });
+                    }
+                    i += 1;
+                    segments.push(PathSegment::Wildcard);
+                } else if chars.get(i) == Some(&'\'') {
+                    i += 1;
+                    let start = i;
+                    while i < chars.len() && chars[i] != '\'' {
+                        i += 1;
+                    }
+                    if i >= chars.len() {
+                        return Err(PathError { pos: i });
+                    }
+                    let key: String = chars[start..i].iter().collect();
+                    i += 1; // Skip the closing quote.
+                    if chars.get(i) != Some(&']') {
+                        return Err(PathError { pos: i });
+                    }
+                    i += 1;
+                    segments.push(PathSegment::Key(key));
+                } else {
+                    let start = i;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                    if start == i || chars.get(i) != Some(&']') {
+                        return Err(PathError { pos: i });
+                    }
+                    let index: usize = chars[start..i].iter().collect::<String>().parse()
+                        .map_err(|_| PathError { pos: start })?;
+                    i += 1;
+                    segments.push(PathSegment::Index(index));
+                }
+            },
+            _ => return Err(PathError { pos: i }),
+        }
+    }
+
+    Ok(segments)
+}
+
+// Pushes `node` itself and every descendant reachable through it onto
+// `worklist`, so a later segment can re-match against all of them at once.
+fn collect_descendants<'a>(node: &'a JSONValue, worklist: &mut Vec<&'a JSONValue>) {
+    worklist.push(node);
+    match node {
+        JSONValue::Obj(hm) => {
+            for v in hm.values() {
+                collect_descendants(v, worklist);
+            }
+        },
+        JSONValue::Arr(arr) => {
+            for v in arr.iter() {
+                collect_descendants(v, worklist);
+            }
+        },
+        _ => {},
+    }
+}
+
+// Selects nodes from a parsed JSON document using a JSONPath expression.
+// Supports `$`, `.key`/`['key']`, `[n]`, `[*]`/`.*`, and the recursive
+// descent operator `..key`.
+pub fn query<'a>(root: &'a JSONValue, path: &str) -> PathResult<Vec<&'a JSONValue>> {
+    let segments = tokenize_path(path)?;
+    let mut current: Vec<&'a JSONValue> = vec![root];
+
+    for segment in segments {
+        let mut next = Vec::new();
+        match segment {
+            PathSegment::Key(key) => {
+                for node in current {
+                    if let JSONValue::Obj(hm) = node {
+                        if let Some(v) = hm.get(&key) {
+                            next.push(v);
+                        }
+                    }
+                }
+            },
+            PathSegment::Index(index) => {
+                for node in current {
+                    if let JSONValue::Arr(arr) = node {
+                        if let Some(v) = arr.get(index) {
+                            next.push(v);
+                        }
+                    }
+                }
+            },
+            PathSegment::Wildcard => {
+                for node in current {
+                    match node {
+                        JSONValue::Obj(hm) => next.extend(hm.values()),
+                        JSONValue::Arr(arr) => next.extend(arr.iter()),
+                        _ => {},
+                    }
+                }
+            },
+            PathSegment::Recursive(key) => {
+                let mut worklist = Vec::new();
+                for node in current {
+                    collect_descendants(node, &mut worklist);
+                }
+                for node in worklist {
+                    if let JSONValue::Obj(hm) = node {
+                        if let Some(v) = hm.get(&key) {
+                            next.push(v);
+                        }
+                    }
+                }
+            },
+        }
+        current = next;
+    }
+
+    Ok(current)
+}
+
+#[cfg(test)]
+mod tests {
+    mod trailing_content {
+        use super::super::{parse, JSONValue, ParseError};
+
+        #[test]
+        fn test_tokenizer_error_in_trailing_bytes_is_not_swallowed() {
+            let err = parse("true \\q").unwrap_err();
+            assert!(matches!(err, ParseError::UnexpectedToken { .. }));
+        }
+
+        #[test]
+        fn test_tokenizer_error_in_trailing_bytes_after_array_is_not_swallowed() {
+            let err = parse("[1,2] \\q").unwrap_err();
+            assert!(matches!(err, ParseError::UnexpectedToken { .. }));
+        }
+
+        #[test]
+        fn test_valid_trailing_token_is_trailing_characters() {
+            let err = parse("true false").unwrap_err();
+            assert!(matches!(err, ParseError::TrailingCharacters { .. }));
+        }
+
+        #[test]
+        fn test_no_trailing_content_parses_clean() {
+            let value = parse("  [1, 2]  ").unwrap();
+            assert!(matches!(value, JSONValue::Arr(ref v) if v.len() == 2));
+        }
+    }
+
+    mod parse_ref {
+        use super::super::{parse_ref, JSONValueRef, ParseError};
+
+        #[test]
+        fn test_borrows_string_without_escapes() {
+            let input = r#""hello""#;
+            match parse_ref(input).unwrap() {
+                JSONValueRef::Str(std::borrow::Cow::Borrowed(s)) => assert_eq!(s, "hello"),
+                other => panic!("expected a borrowed Cow, got {other:?}"),
+            }
+        }
+
+        #[test]
+        fn test_owns_string_with_escapes() {
+            let input = r#""a\nb""#;
+            match parse_ref(input).unwrap() {
+                JSONValueRef::Str(std::borrow::Cow::Owned(s)) => assert_eq!(s, "a\nb"),
+                other => panic!("expected an owned Cow, got {other:?}"),
+            }
+        }
+
+        #[test]
+        fn test_object_and_array() {
+            let value = parse_ref(r#"{"a": [1, 2.5, true, null]}"#).unwrap();
+            let JSONValueRef::Obj(hm) = value else { panic!("expected an object") };
+            let JSONValueRef::Arr(arr) = hm.get("a").unwrap() else { panic!("expected an array") };
+            assert!(matches!(arr[0], JSONValueRef::I64(1)));
+            assert!(matches!(arr[1], JSONValueRef::F64(n) if n == 2.5));
+            assert!(matches!(arr[2], JSONValueRef::Bool(true)));
+            assert!(matches!(arr[3], JSONValueRef::Null));
+        }
+
+        #[test]
+        fn test_trailing_characters_is_parse_error() {
+            let err = parse_ref("1 2").unwrap_err();
+            assert!(matches!(err, ParseError::TrailingCharacters { .. }));
+        }
+    }
+
+    mod serialize {
+        use super::super::parse;
+
+        #[test]
+        fn test_compact_round_trip() {
+            let value = parse(r#"{"a":[1,2,"b\"c"],"d":null,"e":true}"#).unwrap();
+            let out = value.to_compact_string();
+            assert!(!out.contains(' ') && !out.contains('\n'));
+            let reparsed = parse(&out).unwrap();
+            let a = super::super::query(&reparsed, "$.a[2]").unwrap();
+            assert!(matches!(a.as_slice(), [super::super::JSONValue::Str(s)] if s == "b\"c"));
+        }
+
+        #[test]
+        fn test_pretty_round_trip() {
+            let value = parse(r#"{"a":[1,2],"b":{}}"#).unwrap();
+            let pretty = value.to_string_pretty(2);
+            let reparsed = parse(&pretty).unwrap();
+            let a1 = super::super::query(&reparsed, "$.a[1]").unwrap();
+            assert!(matches!(a1.as_slice(), [super::super::JSONValue::I64(2)]));
+        }
+
+        #[test]
+        fn test_to_string_matches_display_not_compact() {
+            let value = parse(r#"{"a":1}"#).unwrap();
+            assert_eq!(value.to_string(), format!("{value}"));
+            assert_ne!(value.to_string(), value.to_compact_string());
+        }
+
+        #[test]
+        fn test_escapes_control_characters_and_quotes() {
+            let value = parse("\"a\\nb\\tc\\\"d\"").unwrap();
+            assert_eq!(value.to_compact_string(), "\"a\\nb\\tc\\\"d\"");
+        }
+
+        #[test]
+        fn test_empty_containers_stay_on_one_line_when_pretty() {
+            let value = parse("{}").unwrap();
+            assert_eq!(value.to_string_pretty(2), "{}");
+        }
+
+        #[test]
+        fn test_whole_number_float_round_trips_as_float() {
+            let value = parse("1.0e21").unwrap();
+            let out = value.to_compact_string();
+            let reparsed = parse(&out).unwrap();
+            assert!(matches!(reparsed, super::super::JSONValue::F64(n) if n == 1.0e21));
+        }
+
+        #[test]
+        fn test_whole_number_float_literal_round_trips() {
+            let value = parse("5.0").unwrap();
+            let out = value.to_compact_string();
+            assert_eq!(out, "5.0");
+        }
+
+        #[test]
+        fn test_overflowing_exponent_is_invalid_number() {
+            let err = parse("1e400").unwrap_err();
+            assert!(matches!(err, super::super::ParseError::InvalidNumber { .. }));
+        }
+    }
+
+    mod parse_number {
+        use super::super::{parse, JSONValue, ParseError};
+
+        #[test]
+        fn test_positive_int_is_i64() {
+            assert!(matches!(parse("42").unwrap(), JSONValue::I64(42)));
+        }
+
+        #[test]
+        fn test_negative_int_is_i64() {
+            assert!(matches!(parse("-17").unwrap(), JSONValue::I64(-17)));
+        }
+
+        #[test]
+        fn test_large_unsigned_overflowing_i64_is_u64() {
+            let value = parse("18446744073709551615").unwrap();
+            assert!(matches!(value, JSONValue::U64(18446744073709551615)));
+        }
+
+        #[test]
+        fn test_fraction_is_f64() {
+            assert!(matches!(parse("1.5").unwrap(), JSONValue::F64(n) if n == 1.5));
+        }
+
+        #[test]
+        fn test_exponent_is_f64() {
+            assert!(matches!(parse("1e3").unwrap(), JSONValue::F64(n) if n == 1000.0));
+        }
+
+        #[test]
+        fn test_whole_number_without_fraction_stays_integral() {
+            // A bare `1` must not be coerced to `1.0`/F64 the way the
+            // original single-`f64` representation did.
+            assert!(matches!(parse("1").unwrap(), JSONValue::I64(1)));
+        }
+
+        #[test]
+        fn test_leading_zero_is_invalid_number() {
+            let err = parse("01").unwrap_err();
+            assert!(matches!(err, ParseError::InvalidNumber { .. }));
+        }
+
+        #[test]
+        fn test_trailing_dot_is_invalid_number() {
+            let err = parse("1.").unwrap_err();
+            assert!(matches!(err, ParseError::InvalidNumber { .. }));
+        }
+
+        #[test]
+        fn test_bare_minus_is_invalid_number() {
+            let err = parse("-").unwrap_err();
+            assert!(matches!(err, ParseError::InvalidNumber { .. }));
+        }
+    }
+
+    mod parse_errors {
+        use super::super::{parse, parse_ref, JSONValue, ParseError};
+
+        #[test]
+        fn test_trailing_comma_in_array_is_rejected() {
+            assert!(parse("[1,]").is_err());
+        }
+
+        #[test]
+        fn test_trailing_comma_in_object_is_rejected() {
+            let err = parse(r#"{"a":1,}"#).unwrap_err();
+            assert!(matches!(err, ParseError::UnexpectedToken { .. }));
+        }
+
+        #[test]
+        fn test_trailing_comma_rejected_same_in_parse_and_parse_ref() {
+            assert!(parse("[1,]").is_err());
+            assert!(parse_ref("[1,]").is_err());
+            assert!(parse(r#"{"a":1,}"#).is_err());
+            assert!(parse_ref(r#"{"a":1,}"#).is_err());
+        }
+
+        #[test]
+        fn test_empty_array_and_object_still_parse() {
+            assert!(matches!(parse("[]").unwrap(), JSONValue::Arr(v) if v.is_empty()));
+            assert!(matches!(parse("{}").unwrap(), JSONValue::Obj(hm) if hm.is_empty()));
+        }
+
+        #[test]
+        fn test_bare_top_level_bool() {
+            let value = parse("true").unwrap();
+            assert!(matches!(value, JSONValue::Bool(true)));
+        }
+
+        #[test]
+        fn test_bare_top_level_null() {
+            let value = parse("null").unwrap();
+            assert!(matches!(value, JSONValue::Null));
+        }
+
+        #[test]
+        fn test_bare_top_level_int() {
+            let value = parse("42").unwrap();
+            assert!(matches!(value, JSONValue::I64(42)));
+        }
+
+        #[test]
+        fn test_bare_top_level_zero() {
+            let value = parse("0").unwrap();
+            assert!(matches!(value, JSONValue::I64(0)));
+        }
+
+        #[test]
+        fn test_unterminated_string_is_unexpected_eof() {
+            let err = parse("\"abc").unwrap_err();
+            assert!(matches!(err, ParseError::UnexpectedEof { .. }));
+        }
+
+        #[test]
+        fn test_unclosed_object_is_unexpected_eof() {
+            let err = parse("{\"a\": 1").unwrap_err();
+            assert!(matches!(err, ParseError::UnexpectedEof { .. }));
+        }
+
+        #[test]
+        fn test_bad_number_is_invalid_number() {
+            let err = parse("01").unwrap_err();
+            assert!(matches!(err, ParseError::InvalidNumber { .. }));
+        }
+
+        #[test]
+        fn test_error_position_is_one_indexed() {
+            let err = parse("{\n  \"a\": }").unwrap_err();
+            match err {
+                ParseError::InvalidNumber { line, col, .. } => {
+                    assert_eq!(line, 2);
+                    assert_eq!(col, 8);
+                },
+                other => panic!("expected InvalidNumber, got {other:?}"),
+            }
+        }
+    }
+
+    mod query {
+        use super::super::{parse, query, PathError};
+
+        #[test]
+        fn test_key_access() {
+            let doc = parse(r#"{"a": {"b": 1}}"#).unwrap();
+            let found = query(&doc, "$.a.b").unwrap();
+            assert!(matches!(found.as_slice(), [super::super::JSONValue::I64(1)]));
+        }
+
+        #[test]
+        fn test_bracket_key_access() {
+            let doc = parse(r#"{"a": 1}"#).unwrap();
+            let found = query(&doc, "$['a']").unwrap();
+            assert!(matches!(found.as_slice(), [super::super::JSONValue::I64(1)]));
+        }
+
+        #[test]
+        fn test_index_access() {
+            let doc = parse("[10, 20, 30]").unwrap();
+            let found = query(&doc, "$[1]").unwrap();
+            assert!(matches!(found.as_slice(), [super::super::JSONValue::I64(20)]));
+        }
+
+        #[test]
+        fn test_wildcard() {
+            let doc = parse("[1, 2, 3]").unwrap();
+            let found = query(&doc, "$[*]").unwrap();
+            assert_eq!(found.len(), 3);
+        }
+
+        #[test]
+        fn test_recursive_descent() {
+            let doc = parse(r#"{"a": {"x": 1}, "b": [{"x": 2}]}"#).unwrap();
+            let mut found = query(&doc, "$..x").unwrap();
+            found.sort_by_key(|v| match v {
+                super::super::JSONValue::I64(n) => *n,
+                _ => unreachable!(),
+            });
+            assert!(matches!(found.as_slice(), [super::super::JSONValue::I64(1), super::super::JSONValue::I64(2)]));
+        }
+
+        #[test]
+        fn test_malformed_path_is_path_error() {
+            let doc = parse("{}").unwrap();
+            let err = query(&doc, "not a path").unwrap_err();
+            assert!(matches!(err, PathError { .. }));
+        }
+    }
+
+    mod unicode_escape {
+        use super::super::{parse, JSONValue, ParseError};
+
+        #[test]
+        fn test_basic_escape() {
+            let value = parse("\"\\u00e9\"").unwrap();
+            assert!(matches!(value, JSONValue::Str(ref s) if s == "é"));
+        }
+
+        #[test]
+        fn test_surrogate_pair() {
+            let value = parse("\"\\ud83d\\ude00\"").unwrap();
+            assert!(matches!(value, JSONValue::Str(ref s) if s == "😀"));
+        }
+
+        #[test]
+        fn test_lone_high_surrogate_is_parse_error() {
+            let err = parse(r#""\ud83d""#).unwrap_err();
+            assert!(matches!(err, ParseError::InvalidEscape { .. }));
+        }
+
+        #[test]
+        fn test_lone_low_surrogate_is_parse_error() {
+            let err = parse(r#""\ude00""#).unwrap_err();
+            assert!(matches!(err, ParseError::InvalidEscape { .. }));
+        }
+
+        #[test]
+        fn test_short_hex_sequence_is_parse_error() {
+            let err = parse(r#""\u12""#).unwrap_err();
+            assert!(matches!(err, ParseError::InvalidEscape { .. }));
+        }
+
+        #[test]
+        fn test_non_hex_digits_are_parse_error() {
+            let err = parse(r#""\uzzzz""#).unwrap_err();
+            assert!(matches!(err, ParseError::InvalidEscape { .. }));
+        }
+    }
+
+    mod parse_string {
+        use super::super::{Tokenizer, TokenStream, parse_string};
+
+        fn stream_past_open_quote(input: &'static [u8]) -> TokenStream<'static> {
+            let mut stream = TokenStream::new(Tokenizer::new(input));
+            stream.advance().unwrap();
+            stream
+        }
+
+        #[test]
+        fn test_parse_string_1() {
+            let mut stream = stream_past_open_quote(b"\"hello\"");
+            let x = parse_string(&mut stream).unwrap();
+            assert_eq!(x, "hello".to_string());
+        }
+
+        #[test]
+        fn test_parse_string_2() {
+            // An empty string is immediately followed by more tokens here so
+            // that, after consuming the closing quote as if it were content,
+            // the lookahead lands on something other than another `"`.
+            let mut stream = stream_past_open_quote(b"\"\",");
+            let x = parse_string(&mut stream).unwrap();
+            assert_eq!(x, "".to_string());
+        }
+
+        #[test]
+        fn test_parse_string_3() {
+            let mut stream = stream_past_open_quote(b"\"\\\"\"");
+            let x = parse_string(&mut stream).unwrap();
+            assert_eq!(x, "\"".to_string());
+        }
+    }
+}